@@ -0,0 +1,232 @@
+//! Minimal HTTP server for `--serve`: the latest report as JSON at `GET /report`.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Semaphore,
+};
+
+/// Largest request head read before answering `431`; `/report` takes no body.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// Pause after a failed `accept` before trying again.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Time a client gets to send its request head before it is answered `408`.
+const REQUEST_HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections answered at once; further clients wait in the listen backlog.
+const MAX_CONNECTIONS: usize = 64;
+
+/// The JSON of the last successful scan, shared between the scan loop and the server.
+#[derive(Default)]
+pub struct LatestReport(Mutex<Option<String>>);
+
+impl LatestReport {
+    /// Replace the served report.
+    pub fn publish(&self, json: String) {
+        *self.0.lock().expect("latest report lock poisoned") = Some(json);
+    }
+
+    fn get(&self) -> Option<String> {
+        self.0.lock().expect("latest report lock poisoned").clone()
+    }
+}
+
+/// Answer connections on `listener` until the task is dropped, one task per connection.
+///
+/// At most [`MAX_CONNECTIONS`] are open at once, and each gets [`REQUEST_HEAD_TIMEOUT`] to send
+/// its request, so idle clients can't use up the server. A failed `accept` (e.g. out of file
+/// descriptors) is reported and retried after a short pause rather than taking the endpoint
+/// down.
+pub async fn serve(listener: TcpListener, latest: Arc<LatestReport>) {
+    serve_limited(listener, latest, MAX_CONNECTIONS, REQUEST_HEAD_TIMEOUT).await
+}
+
+/// [`serve`] with explicit limits.
+async fn serve_limited(
+    listener: TcpListener,
+    latest: Arc<LatestReport>,
+    max_connections: usize,
+    head_timeout: Duration,
+) {
+    let slots = Arc::new(Semaphore::new(max_connections));
+    loop {
+        let slot = slots
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("the connection semaphore is never closed");
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                eprintln!("⚠️  Warning: --serve failed to accept a connection: {err}");
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        let latest = latest.clone();
+        tokio::spawn(async move {
+            // A client hanging up mid-response is not the server's problem
+            let _ = respond(stream, &latest, head_timeout).await;
+            // Moved in so the slot is only freed once the connection is done
+            drop(slot);
+        });
+    }
+}
+
+/// Read one request head and write the matching response, then close the connection.
+///
+/// Only the report text and fixed status bodies are ever sent, so nothing from the command
+/// line (the RPC URL in particular) can leak into a response.
+async fn respond(
+    mut stream: TcpStream,
+    latest: &LatestReport,
+    head_timeout: Duration,
+) -> std::io::Result<()> {
+    let head = match tokio::time::timeout(head_timeout, read_head(&mut stream)).await {
+        Ok(Ok(Head::Complete(head))) => head,
+        Ok(Ok(Head::TooLarge)) => {
+            return write_response(&mut stream, "431 Request Header Fields Too Large", None).await;
+        }
+        Ok(Ok(Head::Closed)) => return Ok(()),
+        Ok(Err(err)) => return Err(err),
+        Err(_) => return write_response(&mut stream, "408 Request Timeout", None).await,
+    };
+
+    let request_line = head.split(|&byte| byte == b'\r').next().unwrap_or_default();
+    let mut parts = request_line.split(|&byte| byte == b' ');
+    let (method, target) = (parts.next(), parts.next().unwrap_or_default());
+    let path = target
+        .split(|&byte| byte == b'?')
+        .next()
+        .unwrap_or_default();
+    match (method, path) {
+        (Some(b"GET"), b"/report") => match latest.get() {
+            Some(json) => write_response(&mut stream, "200 OK", Some(&json)).await,
+            None => {
+                let body = "{\"error\":\"the first scan has not finished yet\"}\n";
+                write_response(&mut stream, "503 Service Unavailable", Some(body)).await
+            }
+        },
+        (_, b"/report") => write_response(&mut stream, "405 Method Not Allowed", None).await,
+        _ => write_response(&mut stream, "404 Not Found", None).await,
+    }
+}
+
+/// What [`read_head`] got from a client.
+enum Head {
+    /// Everything up to and including the blank line after the headers.
+    Complete(Vec<u8>),
+    /// More than [`MAX_REQUEST_HEAD`] bytes without the blank line.
+    TooLarge,
+    /// The client hung up first.
+    Closed,
+}
+
+/// Read a request head from `stream`.
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<Head> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD {
+            return Ok(Head::TooLarge);
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(Head::Closed);
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(Head::Complete(head))
+}
+
+/// Write a `Connection: close` response with an optional JSON body.
+async fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    body: Option<&str>,
+) -> std::io::Result<()> {
+    let body = body.unwrap_or_default();
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n",
+        body.len()
+    );
+    if status.starts_with("405") {
+        response.push_str("Allow: GET\r\n");
+    }
+    if !body.is_empty() {
+        response.push_str("Content-Type: application/json\r\n");
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(address: std::net::SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn report_route_serves_the_latest_json_only() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let latest = Arc::new(LatestReport::default());
+        let server = tokio::spawn(serve(listener, latest.clone()));
+
+        let request = "GET /report HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let response = get(address, request).await;
+        assert!(response.starts_with("HTTP/1.1 503 "), "{response}");
+
+        latest.publish("{\"total_voting_power_wei\":\"1\"}\n".to_string());
+        let response = get(address, request).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.contains("Content-Type: application/json\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"total_voting_power_wei\":\"1\"}\n"));
+
+        let response = get(address, "GET /report?fresh=1 HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        let response = get(address, "POST /report HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 405 "), "{response}");
+        let response = get(address, "GET /rpc HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404 "), "{response}");
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn idle_clients_time_out_and_hold_back_others_only_until_then() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let latest = Arc::new(LatestReport::default());
+        latest.publish("{}\n".to_string());
+        let timeout = Duration::from_millis(200);
+        let server = tokio::spawn(serve_limited(listener, latest, 1, timeout));
+
+        // A client that never sends a request takes the only slot ...
+        let mut idle = TcpStream::connect(address).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let started = tokio::time::Instant::now();
+        let response = get(address, "GET /report HTTP/1.1\r\n\r\n").await;
+        // ... so the next one is answered once the idle one has timed out
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(started.elapsed() >= timeout / 2, "{:?}", started.elapsed());
+
+        let mut response = String::new();
+        idle.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 408 "), "{response}");
+        server.abort();
+    }
+}
@@ -0,0 +1,58 @@
+//! Helpers for tests built on this crate, behind the `testing` feature.
+
+use alloy_primitives::Address;
+use alloy_provider::RootProvider;
+use alloy_rpc_client::RpcClient;
+use alloy_transport::mock::Asserter;
+
+/// The address made of 20 copies of `byte`, e.g. `0x1111…1111` for `0x11`.
+///
+/// # Examples
+///
+/// ```
+/// use alloy_primitives::Address;
+/// use ldo_delegate_vp::testing::addr_from_u8;
+///
+/// assert_eq!(addr_from_u8(0x11), Address::from([0x11; 20]));
+/// ```
+pub fn addr_from_u8(byte: u8) -> Address {
+    Address::from([byte; 20])
+}
+
+/// A distinct address per `index`, big-endian in the low 8 bytes, for lists longer than
+/// [`addr_from_u8`] covers. Index 0 is the zero address.
+///
+/// # Examples
+///
+/// ```
+/// use ldo_delegate_vp::testing::addr_from_index;
+///
+/// assert_eq!(
+///     addr_from_index(258).to_string(),
+///     "0x0000000000000000000000000000000000000102"
+/// );
+/// ```
+pub fn addr_from_index(index: u64) -> Address {
+    Address::left_padding_from(&index.to_be_bytes())
+}
+
+/// A provider answering from the returned [`Asserter`]'s queue, one pushed response per request.
+///
+/// # Examples
+///
+/// ```
+/// use alloy_primitives::U64;
+/// use alloy_provider::Provider;
+/// use ldo_delegate_vp::testing::mock_provider;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let (asserter, provider) = mock_provider();
+/// asserter.push_success(&U64::from(1));
+/// assert_eq!(provider.get_chain_id().await.unwrap(), 1);
+/// # });
+/// ```
+pub fn mock_provider() -> (Asserter, RootProvider) {
+    let asserter = Asserter::new();
+    let provider = RootProvider::new(RpcClient::mocked(asserter.clone()));
+    (asserter, provider)
+}
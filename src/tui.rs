@@ -0,0 +1,385 @@
+//! Interactive voter browser for `--tui`.
+
+use crate::write_atomic;
+use alloy_primitives::U256;
+use anyhow::Result;
+use ldo_delegate_vp::{VoterPower, VotingPowerReport, format_units_human, matches_address_prefix};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Row, Table, TableState},
+};
+use std::path::Path;
+
+/// Where `c` and `j` export the current view.
+const CSV_EXPORT: &str = "voters-view.csv";
+const JSON_EXPORT: &str = "voters-view.json";
+
+/// Table sort orders, toggled with `s`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    /// Voting power descending, as in the static report.
+    Power,
+    Address,
+}
+
+struct App<'a> {
+    report: &'a VotingPowerReport,
+    decimals: u32,
+    /// Shown in place of an unavailable power.
+    placeholder: &'a str,
+    /// The delegate itself, then every delegated voter.
+    voters: Vec<VoterPower>,
+    /// `voters` filtered by `filter` and sorted.
+    view: Vec<VoterPower>,
+    sort: SortColumn,
+    reversed: bool,
+    filter: String,
+    searching: bool,
+    table: TableState,
+    status: String,
+}
+
+/// Browse `report` until the user quits, restoring the terminal afterwards.
+pub fn run(report: &VotingPowerReport, decimals: u32, placeholder: &str) -> Result<()> {
+    let mut app = App::new(report, decimals, placeholder);
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl<'a> App<'a> {
+    fn new(report: &'a VotingPowerReport, decimals: u32, placeholder: &'a str) -> Self {
+        let voters = std::iter::once(&report.self_power)
+            .chain(&report.active)
+            .chain(&report.inactive)
+            .copied()
+            .collect();
+        let mut app = Self {
+            report,
+            decimals,
+            placeholder,
+            voters,
+            view: Vec::new(),
+            sort: SortColumn::Power,
+            reversed: false,
+            filter: String::new(),
+            searching: false,
+            table: TableState::default().with_selected(0),
+            status: String::new(),
+        };
+        app.refresh();
+        app
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if self.searching {
+                match key.code {
+                    KeyCode::Char(c) => self.filter.push(c),
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                    }
+                    KeyCode::Esc => {
+                        self.filter.clear();
+                        self.searching = false;
+                    }
+                    KeyCode::Enter => self.searching = false,
+                    _ => continue,
+                }
+                self.refresh();
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('/') => self.searching = true,
+                KeyCode::Char('s') => {
+                    self.sort = match self.sort {
+                        SortColumn::Power => SortColumn::Address,
+                        SortColumn::Address => SortColumn::Power,
+                    };
+                    self.refresh();
+                }
+                KeyCode::Char('r') => {
+                    self.reversed = !self.reversed;
+                    self.refresh();
+                }
+                KeyCode::Char('c') => self.export(CSV_EXPORT, self.view_csv()),
+                KeyCode::Char('j') => self.export(JSON_EXPORT, self.view_json()?),
+                KeyCode::Down => self.table.select_next(),
+                KeyCode::Up => self.table.select_previous(),
+                KeyCode::PageDown => self.table.scroll_down_by(20),
+                KeyCode::PageUp => self.table.scroll_up_by(20),
+                KeyCode::Home => self.table.select_first(),
+                KeyCode::End => self.table.select_last(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Rebuild the view after the filter or sort order changed.
+    fn refresh(&mut self) {
+        self.view = self
+            .voters
+            .iter()
+            .copied()
+            .filter(|voter| matches_address_prefix(&voter.address, &self.filter))
+            .collect();
+        match self.sort {
+            SortColumn::Power => self.view.sort_by(|a, b| {
+                b.voting_power
+                    .cmp(&a.voting_power)
+                    .then_with(|| a.address.cmp(&b.address))
+            }),
+            SortColumn::Address => self.view.sort_by_key(|voter| voter.address),
+        }
+        if self.reversed {
+            self.view.reverse();
+        }
+        self.table.select((!self.view.is_empty()).then_some(0));
+    }
+
+    fn export(&mut self, path: &str, contents: String) {
+        self.status = match write_atomic(Path::new(path), &contents) {
+            Ok(()) => format!("{} rows written to {path}", self.view.len()),
+            Err(err) => format!("export failed: {err:#}"),
+        };
+    }
+
+    fn view_csv(&self) -> String {
+        let mut out = String::from("address,voting_power_wei,voting_power_ldo\n");
+        for voter in &self.view {
+            let power = voter.power();
+            out.push_str(&format!(
+                "{},{},{}\n",
+                voter.address,
+                power.wei().map_or_else(String::new, |wei| wei.to_string()),
+                power.exact(self.decimals, "")
+            ));
+        }
+        out
+    }
+
+    fn view_json(&self) -> Result<String> {
+        let voters: Vec<VoterPower> = self
+            .view
+            .iter()
+            .map(|voter| VoterPower {
+                delegation_index: None,
+                ..*voter
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&voters)? + "\n")
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [title, body, totals, help_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let meta = &self.report.meta;
+        let at = match (meta.vote_id, meta.block) {
+            (Some(id), _) => format!("vote #{id}"),
+            (None, Some(block)) => format!("block {block}"),
+            (None, None) => "current".to_string(),
+        };
+        frame.render_widget(
+            Line::from(format!("🗳️  {} — voting power ({at})", meta.delegate)).bold(),
+            title,
+        );
+
+        let rows = self.view.iter().enumerate().map(|(i, voter)| {
+            let power = voter.voting_power;
+            let role = if voter.address == meta.delegate {
+                "self"
+            } else if power.is_zero()
+                || self
+                    .report
+                    .dust
+                    .is_some_and(|dust| power < dust.min_nonzero)
+            {
+                "inactive"
+            } else {
+                "active"
+            };
+            Row::new([
+                format!("#{}", i + 1),
+                voter.address.to_string(),
+                format!(
+                    "{} LDO",
+                    voter.power().human(self.decimals, self.placeholder)
+                ),
+                role.to_string(),
+            ])
+        });
+        let sort = match (self.sort, self.reversed) {
+            (SortColumn::Power, false) => "power ↓",
+            (SortColumn::Power, true) => "power ↑",
+            (SortColumn::Address, false) => "address ↑",
+            (SortColumn::Address, true) => "address ↓",
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Length(42),
+                Constraint::Length(26),
+                Constraint::Length(8),
+            ],
+        )
+        .header(Row::new(["#", "Address", "Voting power", "Role"]).bold())
+        .row_highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(table, body, &mut self.table);
+
+        let view_total: U256 = self.view.iter().map(|voter| voter.voting_power).sum();
+        frame.render_widget(
+            Line::from(format!(
+                "{} of {} addresses · view {} LDO · total {} LDO · sorted by {sort}",
+                self.view.len(),
+                self.voters.len(),
+                format_units_human(view_total, self.decimals),
+                format_units_human(self.report.total_voting_power, self.decimals),
+            )),
+            totals,
+        );
+
+        let help = if self.searching {
+            Line::from(format!(
+                "address prefix: {}█  (Enter keep · Esc clear)",
+                self.filter
+            ))
+        } else if !self.status.is_empty() {
+            Line::from(self.status.as_str()).italic()
+        } else {
+            Line::from("↑↓ move · / search · s sort · r reverse · c CSV · j JSON · q quit").dim()
+        };
+        frame.render_widget(help, help_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Address;
+    use ldo_delegate_vp::{ReportMeta, summarize};
+
+    fn report() -> VotingPowerReport {
+        let meta = ReportMeta {
+            vote_id: Some(180),
+            ..ReportMeta::new(Address::from([0xdd; 20]), Address::ZERO)
+        };
+        summarize(
+            meta,
+            [
+                (meta.delegate, U256::from(5)),
+                (Address::from([0xab; 20]), U256::from(7)),
+                (Address::from([0xac; 20]), U256::ZERO),
+                (Address::from([0x11; 20]), U256::from(9)),
+            ],
+        )
+    }
+
+    #[test]
+    fn view_filters_by_prefix_and_sorts() {
+        let report = report();
+        let mut app = App::new(&report, 18, "—");
+        let firsts = |app: &App| {
+            app.view
+                .iter()
+                .map(|voter| voter.address[0])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(firsts(&app), vec![0x11, 0xab, 0xdd, 0xac]);
+
+        app.filter = "0xA".to_string();
+        app.refresh();
+        assert_eq!(firsts(&app), vec![0xab, 0xac]);
+
+        app.sort = SortColumn::Address;
+        app.reversed = true;
+        app.refresh();
+        assert_eq!(firsts(&app), vec![0xac, 0xab]);
+        assert_eq!(app.table.selected(), Some(0));
+
+        app.filter = "0xff".to_string();
+        app.refresh();
+        assert!(app.view.is_empty());
+        assert_eq!(app.table.selected(), None);
+    }
+
+    #[test]
+    fn exports_cover_only_the_view() {
+        let report = report();
+        let mut app = App::new(&report, 0, "—");
+        app.filter = "11".to_string();
+        app.refresh();
+
+        assert_eq!(
+            app.view_csv(),
+            format!(
+                "address,voting_power_wei,voting_power_ldo\n{},9,9\n",
+                Address::from([0x11; 20])
+            )
+        );
+        let json: serde_json::Value = serde_json::from_str(&app.view_json().unwrap()).unwrap();
+        assert_eq!(json[0]["voting_power_wei"], "9");
+        assert_eq!(json.as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn unavailable_powers_are_not_shown_as_zero() {
+        let mut report = report();
+        report.mark_unavailable([Address::from([0xac; 20])]);
+        let mut app = App::new(&report, 0, "n/a");
+        app.filter = "ac".to_string();
+        app.refresh();
+
+        assert_eq!(
+            app.view_csv(),
+            format!(
+                "address,voting_power_wei,voting_power_ldo\n{},,\n",
+                Address::from([0xac; 20])
+            )
+        );
+        let json: serde_json::Value = serde_json::from_str(&app.view_json().unwrap()).unwrap();
+        assert!(json[0]["voting_power_wei"].is_null());
+    }
+
+    #[test]
+    fn draw_shows_live_totals() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let report = report();
+        let mut app = App::new(&report, 0, "—");
+        app.filter = "ab".to_string();
+        app.refresh();
+        let mut terminal = Terminal::new(TestBackend::new(100, 8)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("1 of 4 addresses · view 7 LDO · total 21 LDO"));
+    }
+}
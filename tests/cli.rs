@@ -0,0 +1,684 @@
+//! End-to-end checks of what the binary writes to stdout.
+
+use std::process::{Command, Output};
+
+/// Nothing listens here, so RPC calls fail fast without network access.
+const UNREACHABLE_RPC: &str = "http://127.0.0.1:1";
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ldo_delegate_vp"))
+        .args(["--rpc-url", UNREACHABLE_RPC])
+        // Retrying the unreachable endpoint would only slow the tests down
+        .args(["--startup-retries", "0"])
+        .args(args)
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .expect("binary runs")
+}
+
+#[test]
+fn quiet_json_dry_run_prints_only_json() {
+    let output = run(&["--dry-run", "--quiet", "--format", "json"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1, "stray stdout lines: {stdout:?}");
+    let estimate: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(estimate["voter_count"], serde_json::Value::Null);
+}
+
+#[test]
+fn json_dry_run_logs_progress_to_stderr() {
+    let output = run(&["--dry-run", "--format", "json"]);
+    assert!(output.status.success());
+
+    serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("RPC:"));
+}
+
+#[test]
+fn quiet_json_error_leaves_stdout_empty() {
+    let output = run(&["--quiet", "--format", "json", "--page-size", "0"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+}
+
+#[test]
+fn json_parse_error_is_json() {
+    let output = run(&["--format", "json", "--page-size", "abc"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+    let message = error["error"]["message"].as_str().unwrap();
+    assert!(
+        message.starts_with("invalid value 'abc' for '--page-size"),
+        "{message}"
+    );
+}
+
+#[test]
+fn invalid_proxy_is_reported_without_credentials() {
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "json",
+        "--proxy",
+        "http://user:secret@[bad",
+    ]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error: serde_json::Value = serde_json::from_str(&stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+    assert!(!stderr.contains("secret"), "{stderr}");
+}
+
+#[test]
+fn zero_delegate_concurrency_is_rejected() {
+    let output = run(&["--quiet", "--format", "json", "--delegate-concurrency", "0"]);
+    assert!(!output.status.success());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+    assert_eq!(
+        error["error"]["message"],
+        "--delegate-concurrency must be >= 1"
+    );
+}
+
+#[test]
+fn no_decoration_drops_spacing_but_keeps_rows() {
+    let output = run(&["--dry-run", "--quiet", "--no-decoration"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.lines().any(str::is_empty), "{stdout:?}");
+    assert!(stdout.starts_with("🧮 DRY RUN"), "{stdout:?}");
+    assert!(stdout.contains("   Voter count unknown"));
+}
+
+#[test]
+fn table_format_rejects_dry_run() {
+    let output = run(&["--format", "table", "--dry-run"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--format table does not support --dry-run")
+    );
+}
+
+#[test]
+fn csv_format_rejects_dry_run() {
+    let output = run(&["--format", "csv", "--dry-run"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--format csv does not support --dry-run (supported: human, json)")
+    );
+}
+
+#[test]
+fn explain_prints_the_plan_without_scanning() {
+    let output = run(&[
+        "--explain",
+        "--quiet",
+        "--format",
+        "json",
+        "--block",
+        "19000000",
+    ]);
+    assert!(output.status.success());
+
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(plan["block"], 19_000_000);
+    assert_eq!(plan["delegates"][0]["voter_count"], serde_json::Value::Null);
+    assert_eq!(plan["total_calls"], serde_json::Value::Null);
+    let methods: Vec<_> = plan["calls"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|call| call["method"].as_str().unwrap())
+        .collect();
+    assert!(methods.contains(&"getVotingPowerMultiple"), "{methods:?}");
+    assert!(!methods.contains(&"getVote"), "{methods:?}");
+}
+
+#[test]
+fn invalid_address_list_is_rejected_before_any_rpc() {
+    let path = std::env::temp_dir().join(format!("addresses-{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"["0x1111111111111111111111111111111111111111", "0xnope"]"#,
+    )
+    .unwrap();
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "json",
+        "--addresses-from-json",
+        path.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&path).unwrap();
+    assert!(!output.status.success());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("\"0xnope\" at index 1")
+    );
+}
+
+#[test]
+fn compare_file_must_be_a_saved_report() {
+    let path = std::env::temp_dir().join(format!("leaderboard-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{"leaderboard": []}"#).unwrap();
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "json",
+        "--compare-file",
+        path.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&path).unwrap();
+    assert!(!output.status.success());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("expected a single-delegate JSON report")
+    );
+}
+
+#[test]
+fn malformed_state_override_is_rejected_before_any_rpc() {
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "json",
+        "--state-override",
+        r#"{"0x1111111111111111111111111111111111111111": {"balance": "lots"}}"#,
+    ]);
+    assert!(!output.status.success());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("invalid --state-override JSON")
+    );
+}
+
+#[test]
+fn voters_only_requires_ndjson() {
+    let output = run(&["--quiet", "--format", "json", "--voters-only"]);
+    assert!(!output.status.success());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(
+        error["error"]["message"],
+        "--format json does not support --voters-only (supported: ndjson)"
+    );
+}
+
+#[test]
+fn count_only_rejects_formats_without_a_single_number() {
+    let output = run(&["--quiet", "--format", "table", "--count-only"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "--format table does not support --count-only (supported: human, json, csv, ndjson, total)"
+        ),
+        "{stderr}"
+    );
+
+    let output = run(&["--quiet", "--count-only", "--voters-only"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
+
+#[test]
+fn partition_outputs_require_a_machine_format() {
+    let output = run(&["--quiet", "--active-out", "active.json"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "--format human does not support --active-out (supported: json, csv, ndjson)"
+        ),
+        "{stderr}"
+    );
+
+    let output = run(&["--quiet", "--inactive-out", "inactive.csv", "--voters-only"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
+
+#[test]
+fn unknown_contract_alias_lists_the_known_ones() {
+    let output = run(&["--quiet", "--contract", "lido-votes"]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("known aliases: lido-voting"), "{stderr}");
+}
+
+#[test]
+fn strict_checksum_accepts_contract_aliases() {
+    let output = run(&[
+        "--strict-checksum",
+        "--contract-address",
+        "lido-voting",
+        "--explain",
+        "--quiet",
+        "--format",
+        "json",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        plan["contract"],
+        "0x2e59a20f205bb85a89c53f1936454680651e618e"
+    );
+}
+
+#[test]
+fn with_current_is_a_comparison() {
+    let output = run(&["--quiet", "--with-current"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--vote-id"));
+
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "oneline",
+        "--vote-id",
+        "180",
+        "--with-current",
+    ]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--format oneline does not support --with-current")
+    );
+}
+
+#[test]
+fn address_prefix_must_be_hex() {
+    let output = run(&["--quiet", "--address-prefix", "0xzz"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'z' is not a hex digit"));
+
+    let output = run(&["--quiet", "--prefix-filters-total"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--address-prefix"));
+}
+
+#[test]
+fn seed_addresses_must_be_addresses_and_need_a_power_scan() {
+    let output = run(&["--quiet", "--seed-addresses", "0x1234,vitalik"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--seed-addresses"));
+
+    let seed = "0x3333333333333333333333333333333333333333";
+    let output = run(&["--quiet", "--voters-only", "--seed-addresses", seed]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
+
+#[test]
+fn hashed_addresses_work_with_the_human_format() {
+    let output = run(&["--quiet", "--emit-addresses-hashed", "--explain"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("SCAN PLAN"));
+}
+
+#[test]
+fn ndjson_address_list_names_the_bad_line() {
+    let path = std::env::temp_dir().join(format!("voters-{}.ndjson", std::process::id()));
+    std::fs::write(
+        &path,
+        "{\"address\":\"0x1111111111111111111111111111111111111111\",\"voting_power_wei\":null}\n{\"role\":\"active\"}\n",
+    )
+    .unwrap();
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "ndjson",
+        "--addresses-from-ndjson",
+        path.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&path).unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("on line 2")
+    );
+}
+
+#[test]
+fn oneline_rejects_several_delegates() {
+    let output = run(&[
+        "--oneline",
+        "--delegate-address",
+        "0x1111111111111111111111111111111111111111,0x2222222222222222222222222222222222222222",
+    ]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--format oneline does not support several --delegate-address values")
+    );
+}
+
+#[test]
+fn several_contracts_reject_a_vote_id() {
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "json",
+        "--vote-id",
+        "180",
+        "--contract-address",
+        "aragon=0x2e59A20f205bB85a89C53f1936454680651E618e",
+        "--contract-address",
+        "0x1111111111111111111111111111111111111111",
+    ]);
+    assert!(!output.status.success());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(
+        error["error"]["message"],
+        "--vote-id supports a single --contract-address"
+    );
+}
+
+#[test]
+fn json_addresses_default_to_lowercase() {
+    let output = run(&["--dry-run", "--quiet", "--format", "json"]);
+    let estimate: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        estimate["delegate"],
+        "0x6d8d914205bb14104c0f95bfadb4b1680ef60ccc"
+    );
+
+    let output = run(&[
+        "--dry-run",
+        "--quiet",
+        "--format",
+        "json",
+        "--address-case",
+        "checksum",
+    ]);
+    let estimate: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        estimate["delegate"],
+        "0x6D8D914205bB14104c0f95BfaDb4B1680EF60CCC"
+    );
+}
+
+#[test]
+fn human_output_follows_address_case() {
+    let output = run(&[
+        "--explain",
+        "--quiet",
+        "--block",
+        "19000000",
+        "--address-case",
+        "upper",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("0x2E59A20F205BB85A89C53F1936454680651E618E"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn watch_rejects_json_without_looping() {
+    let output = run(&["--watch", "5", "--quiet", "--format", "json"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--format json does not support --watch")
+    );
+}
+
+#[test]
+fn watch_rejects_an_address_list_from_stdin() {
+    let output = run(&[
+        "--watch",
+        "5",
+        "--quiet",
+        "--format",
+        "ndjson",
+        "--addresses-from-json",
+        "-",
+    ]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("\"kind\":\"invalid_argument\""), "{stderr}");
+    assert!(stderr.contains("stdin"), "{stderr}");
+}
+
+#[test]
+fn address_list_rejects_the_burn_address_unless_allowed() {
+    let path = std::env::temp_dir().join(format!("suspicious-{}.json", std::process::id()));
+    std::fs::write(&path, r#"["0x000000000000000000000000000000000000dead"]"#).unwrap();
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "json",
+        "--addresses-from-json",
+        path.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "invalid_argument");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("is a burn address at index 0")
+    );
+
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "json",
+        "--on-suspicious-address",
+        "warn",
+        "--addresses-from-json",
+        path.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("Warning: address list entry 0x000000000000000000000000000000000000dEaD")
+    );
+}
+
+#[test]
+fn total_only_keeps_stdout_free_of_banners() {
+    let output = run(&["--total-only", "--vote-id", "180"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "{:?}", output.stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("RPC:"));
+
+    let output = run(&["--total-only", "--dry-run"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--format total does not support --dry-run")
+    );
+}
+
+#[test]
+fn unreachable_endpoint_is_retried_at_startup() {
+    let output = Command::new(env!("CARGO_BIN_EXE_ldo_delegate_vp"))
+        .args(["--rpc-url", UNREACHABLE_RPC, "--startup-retries", "1"])
+        .args(["--format", "json"])
+        .output()
+        .expect("binary runs");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("retrying (1/1)"), "{stderr}");
+    let error: serde_json::Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(error["error"]["kind"], "rpc");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("eth_chainId RPC call failed")
+    );
+}
+
+#[test]
+fn rpc_headers_reach_the_endpoint_but_not_the_logs() {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let rpc_url = format!("http://{}", listener.local_addr().unwrap());
+    let endpoint = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+            let n = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..n]);
+        }
+        stream
+            .write_all(
+                b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )
+            .unwrap();
+        String::from_utf8(request).unwrap().to_ascii_lowercase()
+    });
+    let output = Command::new(env!("CARGO_BIN_EXE_ldo_delegate_vp"))
+        .args(["--rpc-url", &rpc_url, "--startup-retries", "0"])
+        .args(["--rpc-header", "X-Api-Key: topsecret"])
+        .env_remove("RPC_HEADER")
+        .output()
+        .expect("binary runs");
+    assert!(!output.status.success());
+
+    assert!(
+        endpoint
+            .join()
+            .unwrap()
+            .contains("\r\nx-api-key: topsecret\r\n")
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("RPC headers: x-api-key (values hidden)"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("topsecret"), "{stderr}");
+
+    let output = run(&["--quiet", "--rpc-header", "Bearer topsecret"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("expected `Name: Value`"), "{stderr}");
+    assert!(!stderr.contains("topsecret"), "{stderr}");
+}
+
+#[test]
+fn decimals_auto_needs_a_token() {
+    let output = run(&["--quiet", "--decimals", "auto"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--decimals auto requires --token-address"),
+        "{stderr}"
+    );
+
+    let output = run(&["--quiet", "--decimals", "78"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("expected `auto` or 0 to 77"), "{stderr}");
+
+    let token = "0x5A98FcBEA516Cf06857215779Fd812CA3beF1B32";
+    let output = run(&["--dry-run", "--decimals", "auto", "--token-address", token]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("Decimals: 18 (assumed"), "{stderr}");
+}
+
+#[test]
+fn tier_edges_must_increase() {
+    let output = run(&[
+        "--quiet",
+        "--format",
+        "json",
+        "--group-by-tier",
+        "--tier-edges",
+        "10000,1000",
+    ]);
+    assert!(!output.status.success());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(
+        error["error"]["message"],
+        "--tier-edges must be strictly increasing"
+    );
+}
+
+#[test]
+fn progress_and_warnings_stay_off_stdout() {
+    let path = std::env::temp_dir().join(format!("warned-{}.json", std::process::id()));
+    std::fs::write(&path, r#"["0x0000000000000000000000000000000000000000"]"#).unwrap();
+    let output = run(&[
+        "--on-suspicious-address",
+        "warn",
+        "--addresses-from-json",
+        path.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&path).unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "{:?}", output.stdout);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("RPC:"), "{stderr}");
+    assert!(stderr.contains("Warning: address list entry"), "{stderr}");
+
+    let output = run(&["--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("DRY RUN"), "{stdout}");
+    assert!(!stdout.contains("RPC:"), "{stdout}");
+    assert!(!stdout.contains("Voter count unavailable"), "{stdout}");
+}